@@ -0,0 +1,317 @@
+//! Hierarchical cancellation signaling.
+//!
+//! A [`CancellationToken`] can be cancelled directly with [`cancel`], or it
+//! can inherit cancellation from an ancestor: [`child_token`] returns a new
+//! token linked to the one it was called on, and cancelling a token cancels
+//! every token descended from it in one pass. Cancelling a child never
+//! affects its parent or siblings.
+//!
+//! [`cancel`]: CancellationToken::cancel
+//! [`child_token`]: CancellationToken::child_token
+
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    cell::UnsafeCell,
+    future::Future,
+    mem::MaybeUninit,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+const CANCELLED: usize = 1 << 0;
+const WAKER_STORED: usize = 1 << 1;
+const LOCKED: usize = 1 << 2;
+
+struct Node {
+    state: AtomicUsize,
+    waker: UnsafeCell<MaybeUninit<Waker>>,
+    parent: Option<Arc<Node>>,
+    /// Live [`CancellationToken`]s descended directly from this node.
+    /// Guarded by the [`LOCKED`] bit in `state`, not by the allocator: an
+    /// entry going stale (its [`Weak`] failing to upgrade) just means the
+    /// child already dropped and detached, or is in the process of doing so.
+    children: UnsafeCell<Vec<Weak<Node>>>,
+}
+
+unsafe impl Send for Node {}
+unsafe impl Sync for Node {}
+
+/// A handle to a hierarchical cancellation signal.
+///
+/// See the [module-level documentation](self) for more.
+pub struct CancellationToken {
+    node: Arc<Node>,
+}
+
+impl CancellationToken {
+    /// Creates a new token with no parent.
+    #[inline]
+    pub fn new() -> Self {
+        Self { node: Arc::new(Node::new(None)) }
+    }
+
+    /// Creates a new token linked to this one.
+    ///
+    /// Cancelling `self`, or any of its ancestors, cancels the returned
+    /// token. Cancelling the returned token has no effect on `self`.
+    pub fn child_token(&self) -> Self {
+        let child = Arc::new(Node::new(Some(Arc::clone(&self.node))));
+        let already_cancelled = self.node.with_children(|children| {
+            if self.node.is_cancelled() {
+                true
+            } else {
+                children.push(Arc::downgrade(&child));
+                false
+            }
+        });
+        if already_cancelled {
+            Node::cancel(&child);
+        }
+        Self { node: child }
+    }
+
+    /// Cancels this token and every token descended from it.
+    #[inline]
+    pub fn cancel(&self) {
+        Node::cancel(&self.node);
+    }
+
+    /// Returns `true` if this token, or one of its ancestors, has been
+    /// cancelled.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.node.is_cancelled()
+    }
+
+    /// Returns a [`Future`] that resolves once this token is cancelled.
+    #[inline]
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled { token: self }
+    }
+}
+
+impl Default for CancellationToken {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for CancellationToken {
+    fn drop(&mut self) {
+        if let Some(parent) = &self.node.parent {
+            let this = Arc::downgrade(&self.node);
+            parent.with_children(|children| children.retain(|child| !child.ptr_eq(&this)));
+        }
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`].
+pub struct Cancelled<'a> {
+    token: &'a CancellationToken,
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.token.node.poll_cancelled(cx)
+    }
+}
+
+impl Node {
+    #[inline]
+    fn new(parent: Option<Arc<Node>>) -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+            waker: UnsafeCell::new(MaybeUninit::zeroed()),
+            parent,
+            children: UnsafeCell::new(Vec::new()),
+        }
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.state.load(Ordering::Acquire) & CANCELLED != 0
+    }
+
+    /// Runs `f` against the current state word in a compare-and-swap loop,
+    /// retrying with the freshly observed state on spurious failure.
+    #[inline]
+    fn update<R, E>(
+        &self,
+        mut current: usize,
+        success: Ordering,
+        failure: Ordering,
+        mut f: impl FnMut(&mut usize) -> Result<R, E>,
+    ) -> Result<R, E> {
+        loop {
+            let mut new = current;
+            let result = f(&mut new)?;
+            match self.state.compare_exchange_weak(current, new, success, failure) {
+                Ok(_) => break Ok(result),
+                Err(state) => current = state,
+            }
+        }
+    }
+
+    /// Runs `f` against the child list, holding the [`LOCKED`] bit for the
+    /// duration so `cancel` and `child_token` can't observe or mutate the
+    /// list concurrently.
+    fn with_children<R>(&self, f: impl FnOnce(&mut Vec<Weak<Node>>) -> R) -> R {
+        let mut current = self.state.load(Ordering::Acquire);
+        loop {
+            if current & LOCKED != 0 {
+                current = self.state.load(Ordering::Acquire);
+                continue;
+            }
+            match self.state.compare_exchange_weak(
+                current,
+                current | LOCKED,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(observed) => current = observed,
+            }
+        }
+        let result = f(unsafe { &mut *self.children.get() });
+        self.state.fetch_and(!LOCKED, Ordering::Release);
+        result
+    }
+
+    /// Sets the cancelled bit on `root` and every live node descended from
+    /// it, waking any parked [`Cancelled`] future along the way.
+    ///
+    /// Walks the tree with an explicit stack rather than recursing into
+    /// children: recursion depth would equal tree depth, and a deep token
+    /// hierarchy can run out of stack on the small, fixed stacks this crate
+    /// targets. Each node's [`with_children`](Node::with_children) lock is
+    /// also only ever held to snapshot that one node's direct children, never
+    /// across the rest of the walk, so a node's children list can't be
+    /// starved by work happening elsewhere in the tree.
+    fn cancel(root: &Arc<Node>) {
+        let mut pending: Vec<Arc<Node>> = Vec::from([Arc::clone(root)]);
+        while let Some(node) = pending.pop() {
+            let claimed =
+                node.update(node.state.load(Ordering::Acquire), Ordering::AcqRel, Ordering::Acquire, |state| {
+                    if *state & CANCELLED != 0 {
+                        return Err(());
+                    }
+                    let waking = *state & WAKER_STORED != 0;
+                    *state |= CANCELLED;
+                    Ok(waking)
+                });
+            let waking = match claimed {
+                Ok(waking) => waking,
+                Err(()) => continue,
+            };
+            if waking {
+                unsafe { node.wake() };
+            }
+            node.with_children(|children| {
+                children.retain(|child| match child.upgrade() {
+                    Some(child) => {
+                        pending.push(child);
+                        true
+                    }
+                    None => false,
+                });
+            });
+        }
+    }
+
+    fn poll_cancelled(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_cancelled() {
+            return Poll::Ready(());
+        }
+        unsafe { *self.waker.get() = MaybeUninit::new(cx.waker().clone()) };
+        self.update(self.state.load(Ordering::Acquire), Ordering::AcqRel, Ordering::Acquire, |state| {
+            *state |= WAKER_STORED;
+            Ok::<_, ()>(())
+        })
+        .ok();
+        if self.is_cancelled() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+
+    #[inline]
+    unsafe fn wake(&self) {
+        (*(*self.waker.get()).as_ptr()).wake_by_ref();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{
+        pin::Pin,
+        sync::atomic::AtomicUsize,
+        task::{RawWaker, RawWakerVTable},
+    };
+
+    struct Counter(AtomicUsize);
+
+    impl Counter {
+        fn to_waker(&'static self) -> Waker {
+            unsafe fn clone(counter: *const ()) -> RawWaker {
+                RawWaker::new(counter, &VTABLE)
+            }
+            unsafe fn wake(counter: *const ()) {
+                (*(counter as *const Counter)).0.fetch_add(1, Ordering::SeqCst);
+            }
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake, drop);
+            unsafe { Waker::from_raw(RawWaker::new(self as *const _ as *const (), &VTABLE)) }
+        }
+    }
+
+    #[test]
+    fn cancel_wakes_pending_future() {
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let token = CancellationToken::new();
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut token.cancelled()).poll(&mut cx), Poll::Pending);
+        token.cancel();
+        assert_eq!(COUNTER.0.load(Ordering::SeqCst), 1);
+        assert!(token.is_cancelled());
+        assert_eq!(Pin::new(&mut token.cancelled()).poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn cancel_propagates_to_children_not_parent() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let sibling = parent.child_token();
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+        assert!(!sibling.is_cancelled());
+        parent.cancel();
+        assert!(sibling.is_cancelled());
+    }
+
+    #[test]
+    fn child_token_of_cancelled_parent_is_cancelled() {
+        let parent = CancellationToken::new();
+        parent.cancel();
+        let child = parent.child_token();
+        assert!(child.is_cancelled());
+    }
+
+    #[test]
+    fn dropped_child_detaches_from_parent() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        parent.node.with_children(|children| assert_eq!(children.len(), 1));
+        drop(child);
+        parent.node.with_children(|children| assert_eq!(children.len(), 0));
+    }
+}
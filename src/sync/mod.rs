@@ -0,0 +1,5 @@
+//! Synchronization primitives for communicating across asynchronous tasks
+//! and interrupts.
+
+pub mod cancel_token;
+pub mod spsc;
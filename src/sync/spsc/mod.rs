@@ -0,0 +1,116 @@
+//! Single-consumer channel primitives. The [`pulse`] and [`tick`] senders
+//! can be cloned to feed one receiver from several producers; a [`watch`]
+//! channel has exactly one sender.
+//!
+//! Each concrete channel ([`pulse`], [`tick`], [`watch`]) stores its own
+//! payload next to a shared state word and picks its own bit layout for that
+//! word; this module only knows about the bits every channel needs to agree
+//! on: completion and the two waker-stored flags. The [`SpscInner`] and
+//! [`SpscInnerErr`] traits factor out the waker-parking and compare-and-swap
+//! dance so each channel only has to implement the payload-specific parts.
+
+pub mod pulse;
+pub mod tick;
+pub mod watch;
+
+use core::{mem::MaybeUninit, ops::BitAnd, sync::atomic::Ordering, task::Waker};
+
+/// Shared state-word operations for an spsc channel's `Inner`.
+pub(crate) trait SpscInner<A, T: Copy + PartialEq + BitAnd<Output = T>> {
+    /// Bit set once either half has gone away, so no more data will flow.
+    const COMPLETE: T;
+    /// Bit set while a [`Waker`] is stored in the receiver's waker slot.
+    const RX_WAKER_STORED: T;
+    /// Bit set while a [`Waker`] is stored in the sender's waker slot.
+    const TX_WAKER_STORED: T;
+    /// The zero value of the state word.
+    const ZERO: T;
+
+    /// Loads the current state word.
+    fn state_load(&self, order: Ordering) -> T;
+
+    /// Attempts to swap the state word, as in
+    /// [`AtomicUsize::compare_exchange_weak`].
+    fn compare_exchange_weak(
+        &self,
+        current: T,
+        new: T,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<T, T>;
+
+    /// Returns a mutable reference to the receiver's waker slot.
+    ///
+    /// # Safety
+    ///
+    /// Must only be accessed while holding the corresponding `*_WAKER_STORED`
+    /// bit, which guarantees exclusive access.
+    unsafe fn rx_waker_mut(&self) -> &mut MaybeUninit<Waker>;
+
+    /// Returns a mutable reference to the sender's waker slot.
+    ///
+    /// # Safety
+    ///
+    /// Must only be accessed while holding the corresponding `*_WAKER_STORED`
+    /// bit, which guarantees exclusive access.
+    unsafe fn tx_waker_mut(&self) -> &mut MaybeUninit<Waker>;
+
+    /// Runs `f` against the current state word in a compare-and-swap loop,
+    /// retrying with the freshly observed state on spurious failure.
+    #[inline]
+    fn update<R, E>(
+        &self,
+        mut current: T,
+        success: Ordering,
+        failure: Ordering,
+        mut f: impl FnMut(&mut T) -> Result<R, E>,
+    ) -> Result<R, E> {
+        loop {
+            let mut new = current;
+            let result = f(&mut new)?;
+            match self.compare_exchange_weak(current, new, success, failure) {
+                Ok(_) => break Ok(result),
+                Err(state) => current = state,
+            }
+        }
+    }
+
+    /// Wakes the stored receiver waker by reference, leaving it in place so
+    /// a burst of updates can each notify without re-storing a waker.
+    #[inline]
+    unsafe fn wake_rx(&self) {
+        (*self.rx_waker_mut().as_ptr()).wake_by_ref();
+    }
+
+    /// Wakes the stored sender waker by reference, leaving it in place so a
+    /// burst of updates can each notify without re-storing a waker.
+    #[inline]
+    unsafe fn wake_tx(&self) {
+        (*self.tx_waker_mut().as_ptr()).wake_by_ref();
+    }
+
+    /// Returns `true` if the channel has been marked complete, meaning the
+    /// other half has gone away.
+    #[inline]
+    fn is_canceled(&self) -> bool {
+        self.state_load(Ordering::Acquire) & Self::COMPLETE != Self::ZERO
+    }
+}
+
+/// Extension of [`SpscInner`] for channels that can carry a terminal error.
+pub(crate) trait SpscInnerErr<A, T: Copy + PartialEq + BitAnd<Output = T>>: SpscInner<A, T> {
+    /// The terminal error type.
+    type Error;
+
+    /// Returns a mutable reference to the stored terminal error.
+    ///
+    /// # Safety
+    ///
+    /// Must only be accessed after the channel has been marked complete.
+    unsafe fn err_mut(&self) -> &mut Option<Self::Error>;
+}
+
+/// Sentinel value for a channel's permit counter meaning "unbounded": the
+/// channel was created with [`pulse::channel`]/[`tick::channel`] rather than
+/// their `_bounded` counterparts, so sends never wait for a permit.
+pub(crate) const UNBOUNDED: usize = usize::MAX;
@@ -1,6 +1,9 @@
-//! A single-producer, single-consumer queue for sending pulses across
+//! A multi-producer, single-consumer queue for sending pulses across
 //! asynchronous tasks.
 //!
+//! [`Sender`] is [`Clone`], so several interrupt sources can each signal
+//! pulses that accumulate into a single counter consumed by one task.
+//!
 //! See [`channel`] constructor for more.
 
 mod receiver;
@@ -11,7 +14,7 @@ pub use self::{
     sender::{SendError, Sender},
 };
 
-use crate::sync::spsc::{SpscInner, SpscInnerErr};
+use crate::sync::spsc::{SpscInner, SpscInnerErr, UNBOUNDED};
 use alloc::sync::Arc;
 use core::{
     cell::UnsafeCell,
@@ -27,10 +30,28 @@ pub const MAX_CAPACITY: usize = 1 << size_of::<usize>() as u32 * 8 - OPTION_BITS
 const TX_WAKER_STORED: usize = 1 << 0;
 const RX_WAKER_STORED: usize = 1 << 1;
 const COMPLETE: usize = 1 << 2;
-const OPTION_BITS: u32 = 3;
+const ERR_SET: usize = 1 << 3;
+/// Set by [`Receiver::close`]: distinct from `COMPLETE`, it only stops new
+/// pulses from being accepted, it doesn't by itself mean the receiver has
+/// gone away.
+const CLOSE: usize = 1 << 4;
+/// Guards writes to `tx_waker`: since [`Sender`] is [`Clone`], more than one
+/// producer can call [`poll_ready`](sender::Sender::poll_ready) or
+/// [`poll_cancel`](sender::Sender::poll_cancel) concurrently, and the waker
+/// slot only has room for one. Held only across a single clone-and-store, so
+/// it never wraps the kind of unbounded work a blocking lock can't safely
+/// guard on an interrupt-shared structure.
+const TX_WAKER_LOCKED: usize = 1 << 5;
+const OPTION_BITS: u32 = 6;
 
 struct Inner<E> {
     state: AtomicUsize,
+    /// Remaining send permits, or [`UNBOUNDED`] for a channel created with
+    /// [`channel`] rather than [`channel_bounded`].
+    permits: AtomicUsize,
+    /// Number of live [`Sender`] clones. The channel only completes from the
+    /// sending side once this reaches zero.
+    producers: AtomicUsize,
     err: UnsafeCell<Option<E>>,
     rx_waker: UnsafeCell<MaybeUninit<Waker>>,
     tx_waker: UnsafeCell<MaybeUninit<Waker>>,
@@ -41,9 +62,33 @@ struct Inner<E> {
 /// The [`Sender`] half is used to signal a number of pulses. The [`Receiver`]
 /// half is a [`Stream`](futures::stream::Stream) that reads the number of
 /// pulses signaled from the last polling.
+///
+/// The channel is unbounded: the sender never has to wait for the receiver.
+/// To apply backpressure instead, use [`channel_bounded`].
 #[inline]
 pub fn channel<E>() -> (Sender<E>, Receiver<E>) {
-    let inner = Arc::new(Inner::new());
+    let inner = Arc::new(Inner::new(UNBOUNDED));
+    let sender = Sender::new(Arc::clone(&inner));
+    let receiver = Receiver::new(inner);
+    (sender, receiver)
+}
+
+/// Creates a new bounded pulse channel, returning the sender/receiver
+/// halves.
+///
+/// The sender is throttled by a pool of `cap` permits, one consumed per
+/// pulse sent. [`Sender::poll_ready`] resolves once enough permits are
+/// available, parking the producer task in the meantime; the [`Receiver`]
+/// returns permits to the pool as it drains pulses, waking a parked
+/// producer.
+///
+/// # Panics
+///
+/// Panics if `cap` is zero or exceeds [`MAX_CAPACITY`].
+#[inline]
+pub fn channel_bounded<E>(cap: usize) -> (Sender<E>, Receiver<E>) {
+    assert!(cap > 0 && cap <= MAX_CAPACITY, "invalid channel capacity");
+    let inner = Arc::new(Inner::new(cap));
     let sender = Sender::new(Arc::clone(&inner));
     let receiver = Receiver::new(inner);
     (sender, receiver)
@@ -54,9 +99,11 @@ unsafe impl<E: Send> Sync for Inner<E> {}
 
 impl<E> Inner<E> {
     #[inline]
-    fn new() -> Self {
+    fn new(permits: usize) -> Self {
         Self {
             state: AtomicUsize::new(0),
+            permits: AtomicUsize::new(permits),
+            producers: AtomicUsize::new(1),
             err: UnsafeCell::new(None),
             rx_waker: UnsafeCell::new(MaybeUninit::zeroed()),
             tx_waker: UnsafeCell::new(MaybeUninit::zeroed()),
@@ -196,4 +243,95 @@ mod tests {
         assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
         assert_eq!(COUNTER.0.load(Ordering::SeqCst), 4);
     }
+
+    #[test]
+    fn bounded_throttles_producer() {
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let (mut tx, mut rx) = channel_bounded::<()>(1);
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(tx.poll_ready(1, &mut cx), Poll::Ready(Ok(())));
+        assert_eq!(tx.send(1).unwrap(), ());
+        assert_eq!(tx.poll_ready(1, &mut cx), Poll::Pending);
+        assert_eq!(
+            Pin::new(&mut rx).poll_next(&mut cx),
+            Poll::Ready(Some(Ok(NonZeroUsize::new(1).unwrap())))
+        );
+        assert_eq!(tx.poll_ready(1, &mut cx), Poll::Ready(Ok(())));
+        assert_eq!(COUNTER.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn bounded_poll_ready_waits_for_enough_permits() {
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let (mut tx, mut rx) = channel_bounded::<()>(2);
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(tx.poll_ready(2, &mut cx), Poll::Ready(Ok(())));
+        assert_eq!(tx.send(1).unwrap(), ());
+        assert_eq!(tx.poll_ready(2, &mut cx), Poll::Pending);
+        assert_eq!(
+            Pin::new(&mut rx).poll_next(&mut cx),
+            Poll::Ready(Some(Ok(NonZeroUsize::new(1).unwrap())))
+        );
+        assert_eq!(tx.poll_ready(2, &mut cx), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn cloned_senders_accumulate_and_complete_together() {
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let (mut tx1, mut rx) = channel::<()>();
+        let mut tx2 = tx1.clone();
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(tx1.send(1).unwrap(), ());
+        assert_eq!(tx2.send(1).unwrap(), ());
+        assert_eq!(
+            Pin::new(&mut rx).poll_next(&mut cx),
+            Poll::Ready(Some(Ok(NonZeroUsize::new(2).unwrap())))
+        );
+        drop(tx1);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+        drop(tx2);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn closed_receiver_rejects_new_sends() {
+        let (mut tx, mut rx) = channel::<()>();
+        assert_eq!(tx.send(1).unwrap(), ());
+        rx.close();
+        assert_eq!(tx.send(1).unwrap_err(), SendError::Closed);
+        assert_eq!(rx.try_recv(), NonZeroUsize::new(1));
+        assert_eq!(rx.try_recv(), None);
+    }
+
+    #[test]
+    fn try_recv_drains_without_a_waker() {
+        let (mut tx, mut rx) = channel::<()>();
+        assert_eq!(rx.try_recv(), None);
+        assert_eq!(tx.send(1).unwrap(), ());
+        assert_eq!(tx.send(2).unwrap(), ());
+        assert_eq!(rx.try_recv(), NonZeroUsize::new(3));
+        assert_eq!(rx.try_recv(), None);
+    }
+
+    #[test]
+    fn sink_send_and_close() {
+        use futures::sink::Sink;
+
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let (mut tx, mut rx) = channel::<()>();
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut tx).poll_ready(&mut cx), Poll::Ready(Ok(())));
+        Pin::new(&mut tx).start_send(NonZeroUsize::new(2).unwrap()).unwrap();
+        assert_eq!(Pin::new(&mut tx).poll_flush(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(Pin::new(&mut tx).poll_close(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(
+            Pin::new(&mut rx).poll_next(&mut cx),
+            Poll::Ready(Some(Ok(NonZeroUsize::new(2).unwrap())))
+        );
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
+    }
 }
@@ -0,0 +1,294 @@
+use super::{Inner, COMPLETE, OPTION_BITS, RX_WAKER_STORED, TX_WAKER_LOCKED, TX_WAKER_STORED};
+use crate::sync::spsc::{SpscInner, UNBOUNDED};
+use alloc::sync::Arc;
+use core::{
+    cell::Cell,
+    mem::MaybeUninit,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::atomic::Ordering::*,
+    task::{Context, Poll, Waker},
+};
+use futures::sink::Sink;
+
+/// The sending-half of [`pulse::channel`](super::channel).
+pub struct Sender<E> {
+    inner: Arc<Inner<E>>,
+    /// Set once this handle has been closed via [`Sink::poll_close`], so
+    /// `Drop` doesn't also decrement the shared producer count.
+    closed: Cell<bool>,
+}
+
+/// Error returned from [`Sender::send`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendError {
+    /// The corresponding [`Receiver`](super::Receiver) was dropped.
+    Canceled,
+    /// The channel's pulse counter would overflow, or (for a channel created
+    /// with [`channel_bounded`](super::channel_bounded)) no send permit is
+    /// currently available.
+    Overflow,
+    /// The corresponding [`Receiver`](super::Receiver) was
+    /// [closed](super::Receiver::close).
+    Closed,
+}
+
+impl<E> Sender<E> {
+    #[inline(always)]
+    pub(super) fn new(inner: Arc<Inner<E>>) -> Self {
+        Self { inner, closed: Cell::new(false) }
+    }
+
+    /// Signals `count` pulses across the channel.
+    ///
+    /// For a bounded channel, this consumes one permit per pulse and fails
+    /// with [`SendError::Overflow`] if not enough permits are available; use
+    /// [`poll_ready`](Sender::poll_ready) with the same `count` to wait for
+    /// enough permits instead of failing immediately.
+    #[inline]
+    pub fn send(&mut self, count: usize) -> Result<(), SendError> {
+        self.inner.send(count)
+    }
+
+    /// Polls whether this [`Sender`] currently holds at least `count`
+    /// permits, enough for a subsequent [`send(count)`](Sender::send) to
+    /// succeed, parking the current task on the producer's waker slot
+    /// otherwise.
+    ///
+    /// Channels created with [`channel`](super::channel) are always ready.
+    #[inline]
+    pub fn poll_ready(&mut self, count: usize, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
+        self.inner.poll_ready(count, cx)
+    }
+
+    /// Completes this stream with an error.
+    ///
+    /// If the value is successfully enqueued, then `Ok(())` is returned. If
+    /// the receiving end was dropped before this function was called, then
+    /// `Err` is returned with the value provided.
+    #[inline]
+    pub fn send_err(self, err: E) -> Result<(), E> {
+        self.inner.send_err(err)
+    }
+
+    /// Polls this [`Sender`] half to detect whether the [`Receiver`] this has
+    /// paired with has gone away.
+    ///
+    /// # Panics
+    ///
+    /// Like `Future::poll`, this function will panic if it's not called from
+    /// within the context of a task.
+    #[inline]
+    pub fn poll_cancel(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.inner.poll_cancel(cx)
+    }
+
+    /// Tests to see whether this [`Sender`]'s corresponding [`Receiver`] has
+    /// gone away.
+    #[inline(always)]
+    pub fn is_canceled(&self) -> bool {
+        self.inner.is_canceled()
+    }
+}
+
+impl<E> Clone for Sender<E> {
+    /// Creates another producer handle for this channel. Pulses sent through
+    /// either handle accumulate into the same counter; the channel is only
+    /// marked complete once every clone has been dropped.
+    #[inline]
+    fn clone(&self) -> Self {
+        self.inner.producers.fetch_add(1, Relaxed);
+        Self { inner: Arc::clone(&self.inner), closed: Cell::new(false) }
+    }
+}
+
+impl<E> Drop for Sender<E> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.closed.replace(true) {
+            self.inner.drop_tx();
+        }
+    }
+}
+
+impl<E> Sink<NonZeroUsize> for Sender<E> {
+    type Error = SendError;
+
+    /// Only ever resolves `Ready(Ok(()))` on a channel created with
+    /// [`channel`](super::channel): the `Sink` trait's `poll_ready` takes no
+    /// item, so on a bounded channel it has no way to know how many pulses
+    /// the `start_send` it's guarding will carry, and so no way to honor the
+    /// `Sink` contract that a `Ready(Ok(()))` here guarantees that call
+    /// succeeds. Use [`Sender::poll_ready`] with the intended count instead
+    /// of this `Sink` impl on a channel created with
+    /// [`channel_bounded`](super::channel_bounded).
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.inner.permits.load(Acquire) != UNBOUNDED {
+            return Poll::Ready(Err(SendError::Overflow));
+        }
+        this.inner.poll_ready(1, cx)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: NonZeroUsize) -> Result<(), Self::Error> {
+        self.get_mut().inner.send(item.get())
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if !this.closed.replace(true) {
+            this.inner.drop_tx();
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<E> Inner<E> {
+    #[inline]
+    fn try_acquire(&self, count: usize) -> Result<(), SendError> {
+        let mut current = self.permits.load(Acquire);
+        if current == UNBOUNDED {
+            return Ok(());
+        }
+        loop {
+            if current < count {
+                return Err(SendError::Overflow);
+            }
+            match self.permits.compare_exchange_weak(current, current - count, AcqRel, Acquire) {
+                Ok(_) => return Ok(()),
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    /// Adds `count` to the shared pulse counter, rejecting the send before
+    /// it's committed if doing so would overflow. Concurrent [`Sender`]
+    /// clones retry against each other's updates via compare-and-swap rather
+    /// than corrupting the flag bits packed into the same word with a
+    /// blind `fetch_add`.
+    fn send(&self, count: usize) -> Result<(), SendError> {
+        if self.state_load(Relaxed) & super::CLOSE != 0 {
+            return Err(SendError::Closed);
+        }
+        self.try_acquire(count)?;
+        if self.is_canceled() {
+            return Err(SendError::Canceled);
+        }
+        let waking = self.update(self.state_load(Relaxed), AcqRel, Relaxed, |state| {
+            let current = *state >> OPTION_BITS;
+            let updated =
+                current.checked_add(count).filter(|&c| c < super::MAX_CAPACITY).ok_or(SendError::Overflow)?;
+            let waking = *state & RX_WAKER_STORED != 0;
+            *state = (updated << OPTION_BITS) | (*state & ((1 << OPTION_BITS) - 1));
+            Ok(waking)
+        })?;
+        if waking {
+            unsafe { self.wake_rx() };
+        }
+        Ok(())
+    }
+
+    fn poll_ready(&self, count: usize, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
+        if self.is_canceled() {
+            return Poll::Ready(Err(SendError::Canceled));
+        }
+        if self.state_load(Acquire) & super::CLOSE != 0 {
+            return Poll::Ready(Err(SendError::Closed));
+        }
+        if self.permits.load(Acquire) >= count {
+            return Poll::Ready(Ok(()));
+        }
+        self.register_tx_waker(cx.waker());
+        // Re-check after storing the waker to avoid missing permits that
+        // were released concurrently.
+        if self.permits.load(Acquire) >= count {
+            return Poll::Ready(Ok(()));
+        }
+        Poll::Pending
+    }
+
+    /// Stores `err` as the channel's terminal error, unless the channel is
+    /// already complete or another producer already stored one first: with
+    /// multiple [`Sender`] clones, only the first caller to claim the
+    /// [`ERR_SET`](super::ERR_SET) bit wins, so concurrent callers can't
+    /// tear each other's error apart.
+    pub(super) fn send_err(&self, err: E) -> Result<(), E> {
+        let claimed = self.update(self.state_load(Relaxed), AcqRel, Relaxed, |state| {
+            if *state & (COMPLETE | super::ERR_SET) != 0 {
+                return Err(());
+            }
+            *state |= super::ERR_SET;
+            Ok(())
+        });
+        if claimed.is_err() {
+            return Err(err);
+        }
+        unsafe { *self.err.get() = Some(err) };
+        Ok(())
+    }
+
+    fn poll_cancel(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_canceled() {
+            return Poll::Ready(());
+        }
+        self.register_tx_waker(cx.waker());
+        if self.is_canceled() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+
+    /// Stores a clone of `waker` in the shared `tx_waker` slot and marks it
+    /// stored.
+    ///
+    /// Unlike `rx_waker`, which only ever has one reader, `tx_waker` can be
+    /// written concurrently by any [`Sender`] clone, so the write itself
+    /// needs to be serialized: `TX_WAKER_LOCKED` is claimed first, released
+    /// right after the store, and never held across anything beyond that —
+    /// the last clone to register wins, matching how a single waker slot
+    /// behaves elsewhere in `futures`.
+    fn register_tx_waker(&self, waker: &Waker) {
+        while self
+            .update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+                if *state & TX_WAKER_LOCKED != 0 {
+                    return Err(());
+                }
+                *state |= TX_WAKER_LOCKED;
+                Ok(())
+            })
+            .is_err()
+        {}
+        unsafe { *self.tx_waker.get() = MaybeUninit::new(waker.clone()) };
+        self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            *state = (*state | TX_WAKER_STORED) & !TX_WAKER_LOCKED;
+            Ok::<_, ()>(())
+        })
+        .ok();
+    }
+
+    /// Marks the channel complete once the last [`Sender`] clone is dropped.
+    pub(super) fn drop_tx(&self) {
+        if self.producers.fetch_sub(1, AcqRel) != 1 {
+            return;
+        }
+        let waking = self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            if *state & COMPLETE != 0 {
+                return Err(());
+            }
+            let waking = *state & RX_WAKER_STORED != 0;
+            *state |= COMPLETE;
+            Ok(waking)
+        });
+        if let Ok(true) = waking {
+            unsafe { self.wake_rx() };
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! A multi-producer, single-consumer queue for sending ticks across
+//! asynchronous tasks.
+//!
+//! [`Sender`] is [`Clone`], so several interrupt sources can each signal
+//! ticks that accumulate into a single counter consumed by one task.
+//!
+//! See [`channel`] constructor for more.
+
+mod receiver;
+mod sender;
+
+pub use self::{
+    receiver::Receiver,
+    sender::{SendTickError, Sender},
+};
+
+use crate::sync::spsc::{SpscInner, SpscInnerErr, UNBOUNDED};
+use alloc::sync::Arc;
+use core::{
+    cell::UnsafeCell,
+    mem::{size_of, MaybeUninit},
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Waker,
+};
+
+#[allow(clippy::identity_op)]
+const TX_WAKER_STORED: usize = 1 << 0;
+const RX_WAKER_STORED: usize = 1 << 1;
+const COMPLETE: usize = 1 << 2;
+const ERR_SET: usize = 1 << 3;
+/// Set by [`Receiver::close`]: distinct from `COMPLETE`, it only stops new
+/// ticks from being accepted, it doesn't by itself mean the receiver has
+/// gone away.
+const CLOSE: usize = 1 << 4;
+/// Guards writes to `tx_waker`: since [`Sender`] is [`Clone`], more than one
+/// producer can call [`poll_ready`](sender::Sender::poll_ready) or
+/// [`poll_cancel`](sender::Sender::poll_cancel) concurrently, and the waker
+/// slot only has room for one. Held only across a single clone-and-store, so
+/// it never wraps the kind of unbounded work a blocking lock can't safely
+/// guard on an interrupt-shared structure.
+const TX_WAKER_LOCKED: usize = 1 << 5;
+const OPTION_BITS: u32 = 6;
+
+/// Maximum capacity of the channel.
+pub const MAX_CAPACITY: usize = 1 << size_of::<usize>() as u32 * 8 - OPTION_BITS;
+
+struct Inner<E> {
+    state: AtomicUsize,
+    /// Remaining send permits, or [`UNBOUNDED`] for a channel created with
+    /// [`channel`] rather than [`channel_bounded`].
+    permits: AtomicUsize,
+    /// Number of live [`Sender`] clones. The channel only completes from the
+    /// sending side once this reaches zero.
+    producers: AtomicUsize,
+    err: UnsafeCell<Option<E>>,
+    rx_waker: UnsafeCell<MaybeUninit<Waker>>,
+    tx_waker: UnsafeCell<MaybeUninit<Waker>>,
+}
+
+/// Creates a new tick channel, returning the sender/receiver halves.
+///
+/// The [`Sender`] half is used to signal a tick. The [`Receiver`] half is a
+/// [`Stream`](futures::stream::Stream) that reads the number of ticks
+/// signaled since the last polling.
+///
+/// The channel is unbounded: the sender never has to wait for the receiver.
+/// To apply backpressure instead, use [`channel_bounded`].
+#[inline]
+pub fn channel<E>() -> (Sender<E>, Receiver<E>) {
+    let inner = Arc::new(Inner::new(UNBOUNDED));
+    let sender = Sender::new(Arc::clone(&inner));
+    let receiver = Receiver::new(inner);
+    (sender, receiver)
+}
+
+/// Creates a new bounded tick channel, returning the sender/receiver
+/// halves.
+///
+/// The sender is throttled by a pool of `cap` permits, one consumed per
+/// tick sent. [`Sender::poll_ready`] resolves once a permit is available,
+/// parking the producer task in the meantime; the [`Receiver`] returns
+/// permits to the pool as it drains ticks, waking a parked producer.
+///
+/// # Panics
+///
+/// Panics if `cap` is zero or exceeds [`MAX_CAPACITY`].
+#[inline]
+pub fn channel_bounded<E>(cap: usize) -> (Sender<E>, Receiver<E>) {
+    assert!(cap > 0 && cap <= MAX_CAPACITY, "invalid channel capacity");
+    let inner = Arc::new(Inner::new(cap));
+    let sender = Sender::new(Arc::clone(&inner));
+    let receiver = Receiver::new(inner);
+    (sender, receiver)
+}
+
+unsafe impl<E: Send> Send for Inner<E> {}
+unsafe impl<E: Send> Sync for Inner<E> {}
+
+impl<E> Inner<E> {
+    #[inline]
+    fn new(permits: usize) -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+            permits: AtomicUsize::new(permits),
+            producers: AtomicUsize::new(1),
+            err: UnsafeCell::new(None),
+            rx_waker: UnsafeCell::new(MaybeUninit::zeroed()),
+            tx_waker: UnsafeCell::new(MaybeUninit::zeroed()),
+        }
+    }
+}
+
+impl<E> SpscInner<AtomicUsize, usize> for Inner<E> {
+    const COMPLETE: usize = COMPLETE;
+    const RX_WAKER_STORED: usize = RX_WAKER_STORED;
+    const TX_WAKER_STORED: usize = TX_WAKER_STORED;
+    const ZERO: usize = 0;
+
+    #[inline]
+    fn state_load(&self, order: Ordering) -> usize {
+        self.state.load(order)
+    }
+
+    #[inline]
+    fn compare_exchange_weak(
+        &self,
+        current: usize,
+        new: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<usize, usize> {
+        self.state.compare_exchange_weak(current, new, success, failure)
+    }
+
+    #[inline]
+    unsafe fn rx_waker_mut(&self) -> &mut MaybeUninit<Waker> {
+        &mut *self.rx_waker.get()
+    }
+
+    #[inline]
+    unsafe fn tx_waker_mut(&self) -> &mut MaybeUninit<Waker> {
+        &mut *self.tx_waker.get()
+    }
+}
+
+impl<E> SpscInnerErr<AtomicUsize, usize> for Inner<E> {
+    type Error = E;
+
+    unsafe fn err_mut(&self) -> &mut Option<Self::Error> {
+        &mut *self.err.get()
+    }
+}
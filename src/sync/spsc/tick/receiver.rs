@@ -0,0 +1,163 @@
+use super::{Inner, COMPLETE, OPTION_BITS, RX_WAKER_STORED, TX_WAKER_STORED};
+use crate::sync::spsc::SpscInner;
+use alloc::sync::Arc;
+use core::{
+    mem::MaybeUninit,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::atomic::Ordering::*,
+    task::{Context, Poll},
+};
+use futures::stream::Stream;
+
+/// The receiving-half of [`tick::channel`](super::channel).
+///
+/// This half implements the [`Stream`] trait, returning the number of
+/// ticks signaled since the last polling.
+pub struct Receiver<E> {
+    inner: Arc<Inner<E>>,
+}
+
+impl<E> Receiver<E> {
+    #[inline(always)]
+    pub(super) fn new(inner: Arc<Inner<E>>) -> Self {
+        Self { inner }
+    }
+
+    /// Closes the channel from the receiving side.
+    ///
+    /// Once closed, subsequent [`Sender::send_tick`](super::Sender::send_tick)
+    /// and [`Sender::poll_ready`](super::Sender::poll_ready) calls fail fast
+    /// with [`SendTickError::Closed`](super::SendTickError::Closed). Ticks
+    /// already signaled before the channel was closed are still observed by
+    /// [`poll_next`](Stream::poll_next) or [`try_recv`](Receiver::try_recv).
+    #[inline]
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+
+    /// Synchronously drains the channel's accumulated tick counter, without
+    /// requiring a task context.
+    ///
+    /// Returns `None` if no ticks have been signaled since the last
+    /// `poll_next` or `try_recv` call. Useful for polling the channel from a
+    /// non-async context, such as a fallback main loop.
+    #[inline]
+    pub fn try_recv(&mut self) -> Option<NonZeroUsize> {
+        self.inner.try_recv()
+    }
+}
+
+impl<E> Stream for Receiver<E> {
+    type Item = Result<NonZeroUsize, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next(cx)
+    }
+}
+
+impl<E> Drop for Receiver<E> {
+    #[inline]
+    fn drop(&mut self) {
+        self.inner.drop_rx();
+    }
+}
+
+impl<E> Inner<E> {
+    fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<Result<NonZeroUsize, E>>> {
+        if let Some(count) = self.try_extract() {
+            self.release_permits(count);
+            return Poll::Ready(Some(Ok(NonZeroUsize::new(count).unwrap())));
+        }
+        if self.state_load(Acquire) & COMPLETE != 0 {
+            if let Some(err) = unsafe { self.err_mut() }.take() {
+                return Poll::Ready(Some(Err(err)));
+            }
+            return Poll::Ready(None);
+        }
+        unsafe { *self.rx_waker.get() = MaybeUninit::new(cx.waker().clone()) };
+        self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            *state |= RX_WAKER_STORED;
+            Ok::<_, ()>(())
+        })
+        .ok();
+        // Re-check after storing the waker: a tick sent between the first
+        // extract attempt and the waker store wouldn't otherwise wake us,
+        // since the sender only notifies the receiver if `RX_WAKER_STORED`
+        // was already set at the time it sent.
+        if let Some(count) = self.try_extract() {
+            self.release_permits(count);
+            return Poll::Ready(Some(Ok(NonZeroUsize::new(count).unwrap())));
+        }
+        Poll::Pending
+    }
+
+    /// Atomically swaps the accumulated tick counter out of `state`,
+    /// returning it, or `None` if no ticks are pending.
+    fn try_extract(&self) -> Option<usize> {
+        self.update(self.state_load(Relaxed), AcqRel, Relaxed, |state| {
+            let count = *state >> OPTION_BITS;
+            if count > 0 {
+                *state &= (1 << OPTION_BITS) - 1;
+                Ok(count)
+            } else {
+                Err(())
+            }
+        })
+        .ok()
+    }
+
+    /// Sets the [`CLOSE`](super::CLOSE) bit, waking a producer parked on
+    /// [`Sender::poll_ready`](super::Sender::poll_ready) if one is stored so
+    /// it observes [`SendTickError::Closed`](super::SendTickError::Closed) on
+    /// its next poll.
+    fn close(&self) {
+        let waking = self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            if *state & super::CLOSE != 0 {
+                return Err(());
+            }
+            let waking = *state & TX_WAKER_STORED != 0;
+            *state |= super::CLOSE;
+            Ok(waking)
+        });
+        if let Ok(true) = waking {
+            unsafe { self.wake_tx() };
+        }
+    }
+
+    /// Synchronously drains the accumulated tick counter, or returns `None`
+    /// if no ticks are pending.
+    fn try_recv(&self) -> Option<NonZeroUsize> {
+        let count = self.try_extract()?;
+        self.release_permits(count);
+        NonZeroUsize::new(count)
+    }
+
+    /// Returns `count` permits to the pool, waking a producer parked on
+    /// [`Sender::poll_ready`](super::Sender::poll_ready) if one is stored.
+    ///
+    /// A no-op for a channel created with [`channel`](super::channel).
+    fn release_permits(&self, count: usize) {
+        if self.permits.load(Relaxed) == crate::sync::spsc::UNBOUNDED {
+            return;
+        }
+        self.permits.fetch_add(count, AcqRel);
+        if self.state_load(Acquire) & TX_WAKER_STORED != 0 {
+            unsafe { self.wake_tx() };
+        }
+    }
+
+    pub(super) fn drop_rx(&self) {
+        let waking = self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            if *state & COMPLETE != 0 {
+                return Err(());
+            }
+            let waking = *state & TX_WAKER_STORED != 0;
+            *state |= COMPLETE;
+            Ok(waking)
+        });
+        if let Ok(true) = waking {
+            unsafe { self.wake_tx() };
+        }
+    }
+}
@@ -1,142 +1,277 @@
-use super::{Inner, COMPLETE, LOCK_BITS, LOCK_MASK, RX_LOCK};
-use alloc::arc::Arc;
-use core::sync::atomic::Ordering::*;
-use futures::Poll;
-use sync::spsc::SpscInner;
-
-/// The sending-half of [`tick::channel`].
-///
-/// [`tick::channel`]: fn.channel.html
+use super::{Inner, COMPLETE, OPTION_BITS, RX_WAKER_STORED, TX_WAKER_LOCKED, TX_WAKER_STORED};
+use crate::sync::spsc::{SpscInner, UNBOUNDED};
+use alloc::sync::Arc;
+use core::{
+    cell::Cell,
+    mem::MaybeUninit,
+    pin::Pin,
+    sync::atomic::Ordering::*,
+    task::{Context, Poll, Waker},
+};
+use futures::sink::Sink;
+
+/// The sending-half of [`tick::channel`](super::channel).
 pub struct Sender<E> {
-  inner: Arc<Inner<E>>,
+    inner: Arc<Inner<E>>,
+    /// Set once this handle has been closed via [`Sink::poll_close`], so
+    /// `Drop` doesn't also decrement the shared producer count.
+    closed: Cell<bool>,
 }
 
 /// Error returned from [`Sender::send_tick`].
-///
-/// [`Sender::send_tick`]: struct.Sender.html#method.send_tick
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SendTickError {
-  /// The corresponding [`Receiver`] is dropped.
-  ///
-  /// [`Receiver`]: struct.Receiver.html
-  Canceled,
-  /// The internal counter overflow. This may happen when the corresponding
-  /// [`Receiver`] is too slow to consume the data.
-  ///
-  /// [`Receiver`]: struct.Receiver.html
-  Overflow,
+    /// The corresponding [`Receiver`](super::Receiver) was dropped.
+    Canceled,
+    /// The channel's tick counter would overflow, or (for a channel created
+    /// with [`channel_bounded`](super::channel_bounded)) no send permit is
+    /// currently available.
+    Overflow,
+    /// The corresponding [`Receiver`](super::Receiver) was
+    /// [closed](super::Receiver::close).
+    Closed,
 }
 
 impl<E> Sender<E> {
-  #[inline(always)]
-  pub(super) fn new(inner: Arc<Inner<E>>) -> Self {
-    Self { inner }
-  }
-
-  /// Sends a single tick across the channel.
-  ///
-  /// [`Receiver`]: struct.Receiver.html
-  #[inline]
-  pub fn send_tick(&mut self) -> Result<(), SendTickError> {
-    self.inner.send_tick()
-  }
-
-  /// Completes this stream with an error.
-  ///
-  /// If the value is successfully enqueued, then `Ok(())` is returned. If the
-  /// receiving end was dropped before this function was called, then `Err` is
-  /// returned with the value provided.
-  ///
-  /// [`Receiver`]: struct.Receiver.html
-  #[inline]
-  pub fn send_err(self, err: E) -> Result<(), E> {
-    self.inner.send_err(err)
-  }
-
-  /// Polls this [`Sender`] half to detect whether the [`Receiver`] this has
-  /// paired with has gone away.
-  ///
-  /// # Panics
-  ///
-  /// Like `Future::poll`, this function will panic if it's not called from
-  /// within the context of a task. In other words, this should only ever be
-  /// called from inside another future.
-  ///
-  /// If you're calling this function from a context that does not have a task,
-  /// then you can use the [`is_canceled`] API instead.
-  ///
-  /// [`Sender`]: struct.Sender.html
-  /// [`Receiver`]: struct.Receiver.html
-  /// [`is_canceled`]: struct.Receiver.html#method.is_canceled
-  #[inline]
-  pub fn poll_cancel(&mut self) -> Poll<(), ()> {
-    self.inner.poll_cancel()
-  }
-
-  /// Tests to see whether this [`Sender`]'s corresponding [`Receiver`] has gone
-  /// away.
-  ///
-  /// [`Sender`]: struct.Sender.html
-  /// [`Receiver`]: struct.Receiver.html
-  #[inline(always)]
-  pub fn is_canceled(&self) -> bool {
-    self.inner.is_canceled()
-  }
+    #[inline(always)]
+    pub(super) fn new(inner: Arc<Inner<E>>) -> Self {
+        Self { inner, closed: Cell::new(false) }
+    }
+
+    /// Sends a single tick across the channel.
+    ///
+    /// For a bounded channel, this consumes one permit and fails with
+    /// [`SendTickError::Overflow`] if no permit is currently available; use
+    /// [`poll_ready`](Sender::poll_ready) to wait for a permit instead of
+    /// failing immediately.
+    #[inline]
+    pub fn send_tick(&mut self) -> Result<(), SendTickError> {
+        self.inner.send_tick()
+    }
+
+    /// Polls whether this [`Sender`] currently holds a permit to send,
+    /// parking the current task on the producer's waker slot otherwise.
+    ///
+    /// Channels created with [`channel`](super::channel) are always ready.
+    #[inline]
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendTickError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    /// Completes this stream with an error.
+    ///
+    /// If the value is successfully enqueued, then `Ok(())` is returned. If
+    /// the receiving end was dropped before this function was called, then
+    /// `Err` is returned with the value provided.
+    #[inline]
+    pub fn send_err(self, err: E) -> Result<(), E> {
+        self.inner.send_err(err)
+    }
+
+    /// Polls this [`Sender`] half to detect whether the [`Receiver`] this has
+    /// paired with has gone away.
+    ///
+    /// # Panics
+    ///
+    /// Like `Future::poll`, this function will panic if it's not called from
+    /// within the context of a task.
+    #[inline]
+    pub fn poll_cancel(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.inner.poll_cancel(cx)
+    }
+
+    /// Tests to see whether this [`Sender`]'s corresponding [`Receiver`] has
+    /// gone away.
+    #[inline(always)]
+    pub fn is_canceled(&self) -> bool {
+        self.inner.is_canceled()
+    }
+}
+
+impl<E> Clone for Sender<E> {
+    /// Creates another producer handle for this channel. Ticks sent through
+    /// either handle accumulate into the same counter; the channel is only
+    /// marked complete once every clone has been dropped.
+    #[inline]
+    fn clone(&self) -> Self {
+        self.inner.producers.fetch_add(1, Relaxed);
+        Self { inner: Arc::clone(&self.inner), closed: Cell::new(false) }
+    }
 }
 
 impl<E> Drop for Sender<E> {
-  #[inline]
-  fn drop(&mut self) {
-    self.inner.drop_tx();
-  }
+    #[inline]
+    fn drop(&mut self) {
+        if !self.closed.replace(true) {
+            self.inner.drop_tx();
+        }
+    }
+}
+
+impl<E> Sink<()> for Sender<E> {
+    type Error = SendTickError;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().inner.poll_ready(cx)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, (): ()) -> Result<(), Self::Error> {
+        self.get_mut().inner.send_tick()
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if !this.closed.replace(true) {
+            this.inner.drop_tx();
+        }
+        Poll::Ready(Ok(()))
+    }
 }
 
 impl<E> Inner<E> {
-  #[inline(always)]
-  fn send_tick(&self) -> Result<(), SendTickError> {
-    self
-      .update(self.state_load(Relaxed), Acquire, Relaxed, |state| {
-        let mut lock = *state & LOCK_MASK;
-        if lock & COMPLETE != 0 {
-          return Err(SendTickError::Canceled);
-        }
-        *state = (*state as isize >> LOCK_BITS) as usize;
-        *state = state.wrapping_add(1);
-        if *state == 0 {
-          return Err(SendTickError::Overflow);
-        }
-        let rx_locked = if lock & RX_LOCK == 0 {
-          lock |= RX_LOCK;
-          true
-        } else {
-          false
-        };
-        *state <<= LOCK_BITS;
-        *state |= lock;
-        if rx_locked {
-          Ok(Some(*state))
-        } else {
-          Ok(None)
-        }
-      })
-      .map(|state| {
-        state.map(|state| {
-          unsafe { (*self.rx_task.get()).as_ref().map(|task| task.notify()) };
-          self.update(state, Release, Relaxed, |state| {
-            *state ^= RX_LOCK;
-            Ok::<(), ()>(())
-          })
+    #[inline]
+    fn try_acquire(&self) -> Result<(), SendTickError> {
+        let mut current = self.permits.load(Acquire);
+        if current == UNBOUNDED {
+            return Ok(());
+        }
+        loop {
+            if current == 0 {
+                return Err(SendTickError::Overflow);
+            }
+            match self.permits.compare_exchange_weak(current, current - 1, AcqRel, Acquire) {
+                Ok(_) => return Ok(()),
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    /// Adds one to the shared tick counter, rejecting the send before it's
+    /// committed if doing so would overflow. Concurrent [`Sender`] clones
+    /// retry against each other's updates via compare-and-swap rather than
+    /// corrupting the flag bits packed into the same word with a blind
+    /// `fetch_add`.
+    fn send_tick(&self) -> Result<(), SendTickError> {
+        if self.state_load(Relaxed) & super::CLOSE != 0 {
+            return Err(SendTickError::Closed);
+        }
+        self.try_acquire()?;
+        if self.is_canceled() {
+            return Err(SendTickError::Canceled);
+        }
+        let waking = self.update(self.state_load(Relaxed), AcqRel, Relaxed, |state| {
+            let current = *state >> OPTION_BITS;
+            let updated =
+                current.checked_add(1).filter(|&c| c < super::MAX_CAPACITY).ok_or(SendTickError::Overflow)?;
+            let waking = *state & RX_WAKER_STORED != 0;
+            *state = (updated << OPTION_BITS) | (*state & ((1 << OPTION_BITS) - 1));
+            Ok(waking)
+        })?;
+        if waking {
+            unsafe { self.wake_rx() };
+        }
+        Ok(())
+    }
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), SendTickError>> {
+        if self.is_canceled() {
+            return Poll::Ready(Err(SendTickError::Canceled));
+        }
+        if self.state_load(Acquire) & super::CLOSE != 0 {
+            return Poll::Ready(Err(SendTickError::Closed));
+        }
+        if self.permits.load(Acquire) != 0 {
+            return Poll::Ready(Ok(()));
+        }
+        self.register_tx_waker(cx.waker());
+        if self.permits.load(Acquire) != 0 {
+            return Poll::Ready(Ok(()));
+        }
+        Poll::Pending
+    }
+
+    /// Stores `err` as the channel's terminal error, unless the channel is
+    /// already complete or another producer already stored one first: with
+    /// multiple [`Sender`] clones, only the first caller to claim the
+    /// [`ERR_SET`](super::ERR_SET) bit wins, so concurrent callers can't
+    /// tear each other's error apart.
+    pub(super) fn send_err(&self, err: E) -> Result<(), E> {
+        let claimed = self.update(self.state_load(Relaxed), AcqRel, Relaxed, |state| {
+            if *state & (COMPLETE | super::ERR_SET) != 0 {
+                return Err(());
+            }
+            *state |= super::ERR_SET;
+            Ok(())
+        });
+        if claimed.is_err() {
+            return Err(err);
+        }
+        unsafe { *self.err.get() = Some(err) };
+        Ok(())
+    }
+
+    fn poll_cancel(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_canceled() {
+            return Poll::Ready(());
+        }
+        self.register_tx_waker(cx.waker());
+        if self.is_canceled() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+
+    /// Stores a clone of `waker` in the shared `tx_waker` slot and marks it
+    /// stored.
+    ///
+    /// Unlike `rx_waker`, which only ever has one reader, `tx_waker` can be
+    /// written concurrently by any [`Sender`] clone, so the write itself
+    /// needs to be serialized: `TX_WAKER_LOCKED` is claimed first, released
+    /// right after the store, and never held across anything beyond that —
+    /// the last clone to register wins, matching how a single waker slot
+    /// behaves elsewhere in `futures`.
+    fn register_tx_waker(&self, waker: &Waker) {
+        while self
+            .update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+                if *state & TX_WAKER_LOCKED != 0 {
+                    return Err(());
+                }
+                *state |= TX_WAKER_LOCKED;
+                Ok(())
+            })
+            .is_err()
+        {}
+        unsafe { *self.tx_waker.get() = MaybeUninit::new(waker.clone()) };
+        self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            *state = (*state | TX_WAKER_STORED) & !TX_WAKER_LOCKED;
+            Ok::<_, ()>(())
+        })
+        .ok();
+    }
+
+    /// Marks the channel complete once the last [`Sender`] clone is dropped.
+    pub(super) fn drop_tx(&self) {
+        if self.producers.fetch_sub(1, AcqRel) != 1 {
+            return;
+        }
+        let waking = self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            if *state & COMPLETE != 0 {
+                return Err(());
+            }
+            let waking = *state & RX_WAKER_STORED != 0;
+            *state |= COMPLETE;
+            Ok(waking)
         });
-      })
-  }
-
-  #[inline(always)]
-  pub fn send_err(&self, err: E) -> Result<(), E> {
-    if self.is_canceled() {
-      Err(err)
-    } else {
-      unsafe { *self.err.get() = Some(err) };
-      Ok(())
-    }
-  }
+        if let Ok(true) = waking {
+            unsafe { self.wake_rx() };
+        }
+    }
 }
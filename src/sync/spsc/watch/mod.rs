@@ -0,0 +1,219 @@
+//! A single-producer, single-consumer channel that publishes the latest
+//! value of a piece of state, coalescing intermediate updates.
+//!
+//! Unlike [`pulse`](super::pulse) and [`tick`](super::tick), which count
+//! discrete events, `watch` tracks a single value: each [`Sender::send`]
+//! replaces the current value, and the [`Receiver`] only ever observes
+//! the most recent one. This is a natural fit for sharing state such as a
+//! sensor reading or a mode flag between an interrupt and a worker task.
+//!
+//! See [`channel`] constructor for more.
+
+mod receiver;
+mod sender;
+
+pub use self::{
+    receiver::Receiver,
+    sender::{SendError, Sender},
+};
+
+use crate::sync::spsc::SpscInner;
+use alloc::sync::Arc;
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+    task::Waker,
+};
+
+#[allow(clippy::identity_op)]
+const TX_WAKER_STORED: usize = 1 << 0;
+const RX_WAKER_STORED: usize = 1 << 1;
+const COMPLETE: usize = 1 << 2;
+
+const INDEX_MASK: u8 = 0b11;
+const NEW_DATA: u8 = 1 << 6;
+
+/// Packs the `back`/`shared`/`front` buffer indices (2 bits each, see
+/// [`Inner::publish`]) plus the "unread data" flag into a single byte.
+#[inline]
+fn pack(back: u8, shared: u8, front: u8, new_data: bool) -> u8 {
+    back | (shared << 2) | (front << 4) | if new_data { NEW_DATA } else { 0 }
+}
+
+/// Unpacks a cursor byte into `(back, shared, front, new_data)`.
+#[inline]
+fn unpack(cursor: u8) -> (u8, u8, u8, bool) {
+    (cursor & INDEX_MASK, (cursor >> 2) & INDEX_MASK, (cursor >> 4) & INDEX_MASK, cursor & NEW_DATA != 0)
+}
+
+struct Inner<T> {
+    state: AtomicUsize,
+    /// Indices of the sender's `back` buffer, the `shared` handoff buffer,
+    /// and the receiver's `front` buffer, plus a flag marking whether
+    /// `shared` holds a value the receiver hasn't consumed yet. See
+    /// [`Inner::publish`] for why three buffers are needed.
+    cursor: AtomicU8,
+    buffers: [UnsafeCell<T>; 3],
+    rx_waker: UnsafeCell<MaybeUninit<Waker>>,
+    tx_waker: UnsafeCell<MaybeUninit<Waker>>,
+}
+
+/// Creates a new watch channel, returning the sender/receiver halves.
+///
+/// The [`Receiver`] is immediately seeded with `init`, so it always has a
+/// value to read even before the [`Sender`] sends one.
+#[inline]
+pub fn channel<T: Clone>(init: T) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner::new(init));
+    let sender = Sender::new(Arc::clone(&inner));
+    let receiver = Receiver::new(inner);
+    (sender, receiver)
+}
+
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Send> Sync for Inner<T> {}
+
+impl<T: Clone> Inner<T> {
+    #[inline]
+    fn new(init: T) -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+            // `new_data` starts set: `shared` (1) and `front` (2) both hold
+            // a clone of `init`, so the receiver's first swap is a harmless
+            // no-op that still leaves it holding the initial value.
+            cursor: AtomicU8::new(pack(0, 1, 2, true)),
+            buffers: [UnsafeCell::new(init.clone()), UnsafeCell::new(init.clone()), UnsafeCell::new(init)],
+            rx_waker: UnsafeCell::new(MaybeUninit::zeroed()),
+            tx_waker: UnsafeCell::new(MaybeUninit::zeroed()),
+        }
+    }
+}
+
+impl<T> Inner<T> {
+    /// Writes `value` into the sender's private `back` buffer, then hands it
+    /// off to the receiver with an atomic index swap rather than a write to
+    /// memory the receiver might be reading.
+    ///
+    /// A plain double buffer isn't enough for this: if the sender published
+    /// twice in a row before the receiver's read of the first value
+    /// finished, it would have to overwrite the very buffer being read. With
+    /// three buffers, `back` is always distinct from both `front` (owned by
+    /// the receiver) and `shared` (the not-yet-consumed handoff slot), so
+    /// the sender always has somewhere safe to write.
+    fn publish(&self, value: T) {
+        let back = (self.cursor.load(Ordering::Relaxed) & INDEX_MASK) as usize;
+        unsafe { *self.buffers[back].get() = value };
+        let mut current = self.cursor.load(Ordering::Relaxed);
+        loop {
+            let (back_i, shared_i, front_i, _) = unpack(current);
+            let updated = pack(shared_i, back_i, front_i, true);
+            match self.cursor.compare_exchange_weak(current, updated, Ordering::AcqRel, Ordering::Relaxed) {
+                Ok(_) => break,
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    /// If the sender has published since the last call, swaps the unread
+    /// value into `front` and returns its buffer index; otherwise `None`.
+    fn consume(&self) -> Option<usize> {
+        let mut current = self.cursor.load(Ordering::Acquire);
+        loop {
+            let (back_i, shared_i, front_i, new_data) = unpack(current);
+            if !new_data {
+                return None;
+            }
+            let updated = pack(back_i, front_i, shared_i, false);
+            match self.cursor.compare_exchange_weak(current, updated, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return Some(shared_i as usize),
+                Err(observed) => current = observed,
+            }
+        }
+    }
+}
+
+impl<T> SpscInner<AtomicUsize, usize> for Inner<T> {
+    const COMPLETE: usize = COMPLETE;
+    const RX_WAKER_STORED: usize = RX_WAKER_STORED;
+    const TX_WAKER_STORED: usize = TX_WAKER_STORED;
+    const ZERO: usize = 0;
+
+    #[inline]
+    fn state_load(&self, order: Ordering) -> usize {
+        self.state.load(order)
+    }
+
+    #[inline]
+    fn compare_exchange_weak(
+        &self,
+        current: usize,
+        new: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<usize, usize> {
+        self.state.compare_exchange_weak(current, new, success, failure)
+    }
+
+    #[inline]
+    unsafe fn rx_waker_mut(&self) -> &mut MaybeUninit<Waker> {
+        &mut *self.rx_waker.get()
+    }
+
+    #[inline]
+    unsafe fn tx_waker_mut(&self) -> &mut MaybeUninit<Waker> {
+        &mut *self.tx_waker.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{
+        pin::Pin,
+        sync::atomic::AtomicUsize,
+        task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    };
+    use futures::stream::Stream;
+
+    struct Counter(AtomicUsize);
+
+    impl Counter {
+        fn to_waker(&'static self) -> Waker {
+            unsafe fn clone(counter: *const ()) -> RawWaker {
+                RawWaker::new(counter, &VTABLE)
+            }
+            unsafe fn wake(counter: *const ()) {
+                (*(counter as *const Counter)).0.fetch_add(1, Ordering::SeqCst);
+            }
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake, drop);
+            unsafe { Waker::from_raw(RawWaker::new(self as *const _ as *const (), &VTABLE)) }
+        }
+    }
+
+    #[test]
+    fn initial_value_is_observed() {
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let (_tx, mut rx) = channel(0_u32);
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(Some(0)));
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+    }
+
+    #[test]
+    fn coalesces_intermediate_updates() {
+        static COUNTER: Counter = Counter(AtomicUsize::new(0));
+        let (mut tx, mut rx) = channel(0_u32);
+        let waker = COUNTER.to_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(Some(0)));
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        tx.send(3).unwrap();
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(Some(3)));
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+        drop(tx);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
+    }
+}
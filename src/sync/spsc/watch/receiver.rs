@@ -0,0 +1,92 @@
+use super::{Inner, COMPLETE, RX_WAKER_STORED};
+use crate::sync::spsc::SpscInner;
+use alloc::sync::Arc;
+use core::{
+    mem::MaybeUninit,
+    pin::Pin,
+    sync::atomic::Ordering::*,
+    task::{Context, Poll},
+};
+use futures::stream::Stream;
+
+/// The receiving-half of [`watch::channel`](super::channel).
+///
+/// This half implements the [`Stream`] trait, yielding the channel's current
+/// value each time it changes. Updates published between two pollings are
+/// coalesced: only the latest value is ever observed.
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Receiver<T> {
+    #[inline(always)]
+    pub(super) fn new(inner: Arc<Inner<T>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T: Clone> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(value) = this.inner.try_read() {
+            return Poll::Ready(Some(value));
+        }
+        if this.inner.state_load(Acquire) & COMPLETE != 0 {
+            return Poll::Ready(None);
+        }
+        unsafe { *this.inner.rx_waker.get() = MaybeUninit::new(cx.waker().clone()) };
+        this.inner
+            .update(this.inner.state_load(Acquire), AcqRel, Acquire, |state| {
+                *state |= RX_WAKER_STORED;
+                Ok::<_, ()>(())
+            })
+            .ok();
+        // Re-check after storing the waker: a value published between the
+        // first read attempt and the waker store wouldn't otherwise wake us,
+        // since the sender only notifies the receiver if `RX_WAKER_STORED`
+        // was already set at the time it sent.
+        if let Some(value) = this.inner.try_read() {
+            return Poll::Ready(Some(value));
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.inner.drop_rx();
+    }
+}
+
+impl<T: Clone> Inner<T> {
+    /// Returns a clone of the latest published value, or `None` if nothing
+    /// new has been published since the last call.
+    ///
+    /// [`consume`](Inner::consume) swaps the unread value into the buffer
+    /// this exclusively owns before cloning it, so unlike a version counter
+    /// over a single shared slot, there's no memory here a concurrent
+    /// [`send`](super::Sender::send) could still be writing.
+    fn try_read(&self) -> Option<T> {
+        let front = self.consume()?;
+        Some(unsafe { (*self.buffers[front].get()).clone() })
+    }
+}
+
+impl<T> Inner<T> {
+    pub(super) fn drop_rx(&self) {
+        let waking = self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            if *state & COMPLETE != 0 {
+                return Err(());
+            }
+            let waking = *state & super::TX_WAKER_STORED != 0;
+            *state |= COMPLETE;
+            Ok(waking)
+        });
+        if let Ok(true) = waking {
+            unsafe { self.wake_tx() };
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{Inner, RX_WAKER_STORED, TX_WAKER_STORED};
+use crate::sync::spsc::SpscInner;
+use alloc::sync::Arc;
+use core::{
+    mem::MaybeUninit,
+    sync::atomic::Ordering::*,
+    task::{Context, Poll},
+};
+
+/// The sending-half of [`watch::channel`](super::channel).
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// Error returned from [`Sender::send`] when the corresponding [`Receiver`]
+/// has been dropped. Carries the value that failed to send.
+///
+/// [`Receiver`]: super::Receiver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> Sender<T> {
+    #[inline(always)]
+    pub(super) fn new(inner: Arc<Inner<T>>) -> Self {
+        Self { inner }
+    }
+
+    /// Publishes `value` as the channel's current value, overwriting
+    /// whatever was there before and waking the [`Receiver`](super::Receiver)
+    /// if it's waiting.
+    #[inline]
+    pub fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+        self.inner.send(value)
+    }
+
+    /// Polls this [`Sender`] half to detect whether the [`Receiver`] this has
+    /// paired with has gone away.
+    ///
+    /// # Panics
+    ///
+    /// Like `Future::poll`, this function will panic if it's not called from
+    /// within the context of a task.
+    #[inline]
+    pub fn poll_cancel(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.inner.poll_cancel(cx)
+    }
+
+    /// Tests to see whether this [`Sender`]'s corresponding
+    /// [`Receiver`](super::Receiver) has gone away.
+    #[inline(always)]
+    pub fn is_canceled(&self) -> bool {
+        self.inner.is_canceled()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.inner.drop_tx();
+    }
+}
+
+impl<T> Inner<T> {
+    fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.is_canceled() {
+            return Err(SendError(value));
+        }
+        self.publish(value);
+        if self.state_load(Acquire) & RX_WAKER_STORED != 0 {
+            unsafe { self.wake_rx() };
+        }
+        Ok(())
+    }
+
+    fn poll_cancel(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_canceled() {
+            return Poll::Ready(());
+        }
+        unsafe { *self.tx_waker.get() = MaybeUninit::new(cx.waker().clone()) };
+        self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            *state |= TX_WAKER_STORED;
+            Ok::<_, ()>(())
+        })
+        .ok();
+        if self.is_canceled() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+
+    pub(super) fn drop_tx(&self) {
+        let waking = self.update(self.state_load(Acquire), AcqRel, Acquire, |state| {
+            if *state & super::COMPLETE != 0 {
+                return Err(());
+            }
+            let waking = *state & RX_WAKER_STORED != 0;
+            *state |= super::COMPLETE;
+            Ok(waking)
+        });
+        if let Ok(true) = waking {
+            unsafe { self.wake_rx() };
+        }
+    }
+}